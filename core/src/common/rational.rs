@@ -0,0 +1,174 @@
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+use num_bigint::{BigInt, ParseBigIntError};
+use num_integer::Integer;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+/// An exact rational number, always kept in lowest terms with a
+/// non-negative denominator. Backed by arbitrary-precision integers
+/// (`BigInt`) so repeated `add`/`mult` across many clauses can't overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rational {
+  pub numer: BigInt,
+  pub denom: BigInt,
+}
+
+impl Rational {
+  pub fn new(numer: impl Into<BigInt>, denom: impl Into<BigInt>) -> Self {
+    let (numer, denom) = (numer.into(), denom.into());
+    assert!(!denom.is_zero(), "rational denominator cannot be zero");
+    let (numer, denom) = if denom.is_negative() { (-numer, -denom) } else { (numer, denom) };
+    let g = numer.abs().gcd(&denom);
+    let g = if g.is_zero() { BigInt::from(1) } else { g };
+    Self { numer: numer / &g, denom: denom / &g }
+  }
+
+  pub fn zero() -> Self {
+    Self { numer: BigInt::from(0), denom: BigInt::from(1) }
+  }
+
+  pub fn one() -> Self {
+    Self { numer: BigInt::from(1), denom: BigInt::from(1) }
+  }
+
+  /// A lossy projection down to `f64`, for display or for comparing
+  /// against the floating-point provenance's results.
+  pub fn to_f64(&self) -> f64 {
+    self.numer.to_f64().unwrap() / self.denom.to_f64().unwrap()
+  }
+}
+
+impl Add for Rational {
+  type Output = Rational;
+
+  fn add(self, other: Rational) -> Rational {
+    let numer = &self.numer * &other.denom + &other.numer * &self.denom;
+    let denom = self.denom * other.denom;
+    Rational::new(numer, denom)
+  }
+}
+
+impl Mul for Rational {
+  type Output = Rational;
+
+  fn mul(self, other: Rational) -> Rational {
+    Rational::new(self.numer * other.numer, self.denom * other.denom)
+  }
+}
+
+impl Sub for Rational {
+  type Output = Rational;
+
+  fn sub(self, other: Rational) -> Rational {
+    let numer = &self.numer * &other.denom - &other.numer * &self.denom;
+    let denom = self.denom * other.denom;
+    Rational::new(numer, denom)
+  }
+}
+
+impl fmt::Display for Rational {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}/{}", self.numer, self.denom)
+  }
+}
+
+/// Why parsing a string as a `Rational` failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RationalParseError {
+  InvalidInt(ParseBigIntError),
+  ZeroDenominator,
+}
+
+impl fmt::Display for RationalParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidInt(e) => write!(f, "{}", e),
+      Self::ZeroDenominator => write!(f, "rational denominator cannot be zero"),
+    }
+  }
+}
+
+impl std::error::Error for RationalParseError {}
+
+impl From<ParseBigIntError> for RationalParseError {
+  fn from(e: ParseBigIntError) -> Self {
+    Self::InvalidInt(e)
+  }
+}
+
+impl FromStr for Rational {
+  type Err = RationalParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (numer, denom) = match s.split_once('/') {
+      Some((numer, denom)) => (numer.parse::<BigInt>()?, denom.parse::<BigInt>()?),
+      None => (s.parse::<BigInt>()?, BigInt::from(1)),
+    };
+    if denom.is_zero() {
+      return Err(RationalParseError::ZeroDenominator);
+    }
+    Ok(Rational::new(numer, denom))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn new_reduces_to_lowest_terms() {
+    let r = Rational::new(4, 8);
+    assert_eq!(r, Rational::new(1, 2));
+  }
+
+  #[test]
+  fn new_normalizes_a_negative_denominator() {
+    let r = Rational::new(1, -2);
+    assert_eq!(r, Rational::new(-1, 2));
+  }
+
+  #[test]
+  fn zero_numerator_reduces_to_canonical_zero() {
+    let r = Rational::new(0, 5);
+    assert_eq!(r, Rational::zero());
+  }
+
+  #[test]
+  fn arithmetic_matches_rational_identities() {
+    let half = Rational::new(1, 2);
+    let third = Rational::new(1, 3);
+    assert_eq!(half.clone() + third.clone(), Rational::new(5, 6));
+    assert_eq!(half.clone() * third.clone(), Rational::new(1, 6));
+    assert_eq!(half - third, Rational::new(1, 6));
+  }
+
+  #[test]
+  fn to_f64_is_a_close_approximation() {
+    assert!((Rational::new(1, 4).to_f64() - 0.25).abs() < 1e-12);
+  }
+
+  #[test]
+  fn from_str_parses_fractions_and_bare_integers() {
+    assert_eq!("3/4".parse::<Rational>().unwrap(), Rational::new(3, 4));
+    assert_eq!("5".parse::<Rational>().unwrap(), Rational::new(5, 1));
+  }
+
+  #[test]
+  fn from_str_rejects_a_zero_denominator_instead_of_panicking() {
+    assert_eq!("1/0".parse::<Rational>().unwrap_err(), RationalParseError::ZeroDenominator);
+  }
+
+  #[test]
+  fn repeated_multiplication_over_many_clauses_does_not_overflow() {
+    // A proof chain far deeper than `i128` (max ~1.7e38) could represent
+    // exactly: 200 halvings is on the order of 1 / 2^200 ≈ 6.2e-61.
+    let mut acc = Rational::one();
+    let half = Rational::new(1, 2);
+    for _ in 0..200 {
+      acc = acc * half.clone();
+    }
+    assert_eq!(acc, Rational::new(1, BigInt::from(2).pow(200)));
+  }
+}
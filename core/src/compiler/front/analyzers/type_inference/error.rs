@@ -0,0 +1,162 @@
+use std::fmt::{self, Display};
+
+use super::super::utils::*;
+use super::*;
+
+/// One frame of context attached to a `TypeInferenceError`, explaining
+/// what the checker was doing when the error occurred, e.g. "while
+/// unifying `i32` with `String`" or "in the body of rule `R`".
+#[derive(Clone, Debug)]
+pub struct ContextFrame {
+  pub message: String,
+  pub span: Option<Span>,
+}
+
+impl ContextFrame {
+  pub fn new(message: impl Into<String>) -> Self {
+    Self { message: message.into(), span: None }
+  }
+
+  pub fn with_span(message: impl Into<String>, span: Span) -> Self {
+    Self { message: message.into(), span: Some(span) }
+  }
+}
+
+impl Display for ContextFrame {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.span {
+      Some(span) => write!(f, "{} ({})", self.message, span),
+      None => write!(f, "{}", self.message),
+    }
+  }
+}
+
+/// An error from the type inference / unification subsystem. Besides the
+/// root cause (`kind`), it carries a stack of `ContextFrame`s describing
+/// the chain of unification/inference steps that led to it, innermost
+/// first.
+#[derive(Clone, Debug)]
+pub struct TypeInferenceError {
+  pub kind: TypeInferenceErrorKind,
+  pub context: Vec<ContextFrame>,
+}
+
+impl TypeInferenceError {
+  pub fn new(kind: TypeInferenceErrorKind) -> Self {
+    Self { kind, context: Vec::new() }
+  }
+
+  /// Push a context frame, innermost call site first.
+  pub fn with_context(mut self, frame: ContextFrame) -> Self {
+    self.context.push(frame);
+    self
+  }
+
+  /// Convenience for attaching a plain message with no span.
+  pub fn attach_context(self, message: impl Into<String>) -> Self {
+    self.with_context(ContextFrame::new(message))
+  }
+
+  /// Convenience for attaching a message together with the span it refers
+  /// to.
+  pub fn attach_context_span(self, message: impl Into<String>, span: Span) -> Self {
+    self.with_context(ContextFrame::with_span(message, span))
+  }
+}
+
+/// Run a fallible recursion step, attaching `message` as a context frame
+/// to any error it returns. Meant to be called at each recursion boundary
+/// in `unification` and `type_inference`, e.g.:
+///
+/// ```ignore
+/// fn check_rule(&mut self, rule: &Rule) -> Result<(), TypeInferenceError> {
+///   in_context(format!("in the body of rule `{}`", rule.name()), || {
+///     self.check_expr(rule.body())
+///   })
+/// }
+/// ```
+pub fn in_context<T>(
+  message: impl Into<String>,
+  step: impl FnOnce() -> Result<T, TypeInferenceError>,
+) -> Result<T, TypeInferenceError> {
+  step().map_err(|e| e.attach_context(message))
+}
+
+impl Display for TypeInferenceError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.kind)?;
+    for frame in &self.context {
+      write!(f, "\n  {}", frame)?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for TypeInferenceError {}
+
+/// The root cause of a `TypeInferenceError`, without any context.
+#[derive(Clone, Debug)]
+pub enum TypeInferenceErrorKind {
+  CannotUnifyTypes { t1: TypeSet, t2: TypeSet },
+  CannotUnifyVariables { v1: String, v2: String },
+  UnknownFunction { name: String },
+  Custom(String),
+}
+
+impl Display for TypeInferenceErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::CannotUnifyTypes { t1, t2 } => write!(f, "cannot unify type `{:?}` with `{:?}`", t1, t2),
+      Self::CannotUnifyVariables { v1, v2 } => write!(f, "cannot unify variable `{}` with `{}`", v1, v2),
+      Self::UnknownFunction { name } => write!(f, "unknown function `{}`", name),
+      Self::Custom(msg) => write!(f, "{}", msg),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn context_frames_display_innermost_first() {
+    let err = TypeInferenceError::new(TypeInferenceErrorKind::Custom("root cause".to_string()))
+      .attach_context("in expression `a + b`")
+      .attach_context("in the body of rule `R`");
+    assert_eq!(err.to_string(), "root cause\n  in expression `a + b`\n  in the body of rule `R`");
+  }
+
+  #[test]
+  fn fresh_error_has_no_context() {
+    let err = TypeInferenceError::new(TypeInferenceErrorKind::Custom("root cause".to_string()));
+    assert!(err.context.is_empty());
+    assert_eq!(err.to_string(), "root cause");
+  }
+
+  #[test]
+  fn in_context_is_a_no_op_on_success() {
+    let result: Result<i32, TypeInferenceError> = in_context("in rule `R`", || Ok(1));
+    assert_eq!(result.unwrap(), 1);
+  }
+
+  #[test]
+  fn in_context_attaches_a_frame_on_failure() {
+    let result: Result<(), TypeInferenceError> = in_context("in rule `R`", || {
+      Err(TypeInferenceError::new(TypeInferenceErrorKind::Custom("root cause".to_string())))
+    });
+    let err = result.unwrap_err();
+    assert_eq!(err.context.len(), 1);
+    assert_eq!(err.to_string(), "root cause\n  in rule `R`");
+  }
+
+  #[test]
+  fn nested_in_context_calls_accumulate_innermost_first() {
+    let result: Result<(), TypeInferenceError> = in_context("in rule `R`", || {
+      in_context("in expression `a + b`", || {
+        Err(TypeInferenceError::new(TypeInferenceErrorKind::Custom("root cause".to_string())))
+      })
+    });
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "root cause\n  in expression `a + b`\n  in rule `R`");
+  }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// The signature of a function: the type of each argument, in order, and
+/// its return type.
+#[derive(Clone, Debug)]
+pub struct FunctionType {
+  pub arg_types: Vec<TypeSet>,
+  pub return_type: TypeSet,
+}
+
+/// Known function signatures, keyed by name.
+#[derive(Clone, Debug, Default)]
+pub struct FunctionTypeTable {
+  functions: HashMap<String, FunctionType>,
+}
+
+impl FunctionTypeTable {
+  pub fn new() -> Self {
+    Self { functions: HashMap::new() }
+  }
+
+  pub fn get(&self, name: &str) -> Option<&FunctionType> {
+    self.functions.get(name)
+  }
+
+  pub fn insert(&mut self, name: impl Into<String>, ty: FunctionType) {
+    self.functions.insert(name.into(), ty);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unknown_function_has_no_signature() {
+    let functions = FunctionTypeTable::new();
+    assert!(functions.get("foo").is_none());
+  }
+
+  #[test]
+  fn insert_then_get_round_trips() {
+    let mut functions = FunctionTypeTable::new();
+    functions.insert(
+      "foo",
+      FunctionType { arg_types: vec![TypeSet::singleton(BaseType::I32)], return_type: TypeSet::singleton(BaseType::Bool) },
+    );
+    assert_eq!(functions.get("foo").unwrap().return_type, TypeSet::singleton(BaseType::Bool));
+  }
+}
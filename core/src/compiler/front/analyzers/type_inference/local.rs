@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Maps local variable names to their currently inferred `TypeSet`,
+/// narrowed in place as unification proceeds.
+#[derive(Clone, Debug, Default)]
+pub struct LocalTypeTable {
+  types: HashMap<String, TypeSet>,
+}
+
+impl LocalTypeTable {
+  pub fn new() -> Self {
+    Self { types: HashMap::new() }
+  }
+
+  /// The current type set of `name`, or `TypeSet::All` if it hasn't been
+  /// constrained yet.
+  pub fn get(&self, name: &str) -> TypeSet {
+    self.types.get(name).cloned().unwrap_or(TypeSet::All)
+  }
+
+  pub fn set(&mut self, name: impl Into<String>, ty: TypeSet) {
+    self.types.insert(name.into(), ty);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unbound_variable_is_unconstrained() {
+    let locals = LocalTypeTable::new();
+    assert_eq!(locals.get("x"), TypeSet::All);
+  }
+
+  #[test]
+  fn set_then_get_round_trips() {
+    let mut locals = LocalTypeTable::new();
+    locals.set("x", TypeSet::singleton(BaseType::I32));
+    assert_eq!(locals.get("x"), TypeSet::singleton(BaseType::I32));
+  }
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+/// A minimal front-end expression: just enough surface (literals,
+/// variables, binary operations, and calls) to recurse type inference
+/// through.
+#[derive(Clone, Debug)]
+pub enum Expr {
+  IntLiteral(i64),
+  Variable(String),
+  Binary { op: String, left: Box<Expr>, right: Box<Expr> },
+  Call { function: String, args: Vec<Expr> },
+}
+
+/// Infer the type of `expr`, recursing into sub-expressions and attaching
+/// a context frame at each recursion boundary (via `in_context`) so an
+/// error produced deep inside an operand or argument accumulates a trail
+/// of frames back up to the top-level call.
+pub fn infer_expr(locals: &mut LocalTypeTable, functions: &FunctionTypeTable, expr: &Expr) -> Result<TypeSet, TypeInferenceError> {
+  match expr {
+    Expr::IntLiteral(_) => Ok(TypeSet::singleton(BaseType::I64)),
+
+    Expr::Variable(name) => {
+      let ty = locals.get(name);
+      if ty == TypeSet::All {
+        Err(
+          TypeInferenceError::new(TypeInferenceErrorKind::Custom(format!("unbound variable `{}`", name)))
+            .attach_context(format!("while inferring the type of `{}`", name)),
+        )
+      } else {
+        Ok(ty)
+      }
+    }
+
+    Expr::Binary { op, left, right } => {
+      let t_left = in_context(format!("in the left operand of `{}`", op), || infer_expr(locals, functions, left))?;
+      let t_right = in_context(format!("in the right operand of `{}`", op), || infer_expr(locals, functions, right))?;
+      t_left.unify(&t_right).ok_or_else(|| {
+        TypeInferenceError::new(TypeInferenceErrorKind::CannotUnifyTypes { t1: t_left.clone(), t2: t_right.clone() })
+          .attach_context(format!("while unifying the operands of `{}`", op))
+      })
+    }
+
+    Expr::Call { function, args } => {
+      let signature = functions
+        .get(function)
+        .ok_or_else(|| TypeInferenceError::new(TypeInferenceErrorKind::UnknownFunction { name: function.clone() }))?
+        .clone();
+
+      if signature.arg_types.len() != args.len() {
+        return Err(
+          TypeInferenceError::new(TypeInferenceErrorKind::Custom(format!(
+            "`{}` expects {} argument(s), got {}",
+            function,
+            signature.arg_types.len(),
+            args.len()
+          )))
+          .attach_context(format!("in the call to `{}`", function)),
+        );
+      }
+
+      for (i, (arg, expected)) in args.iter().zip(signature.arg_types.iter()).enumerate() {
+        let t_arg = in_context(format!("in argument {} of `{}`", i, function), || infer_expr(locals, functions, arg))?;
+        t_arg.unify(expected).ok_or_else(|| {
+          TypeInferenceError::new(TypeInferenceErrorKind::CannotUnifyTypes { t1: t_arg.clone(), t2: expected.clone() })
+            .attach_context(format!("in argument {} of `{}`", i, function))
+        })?;
+      }
+
+      Ok(signature.return_type)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn infers_an_int_literal() {
+    let mut locals = LocalTypeTable::new();
+    let functions = FunctionTypeTable::new();
+    let ty = infer_expr(&mut locals, &functions, &Expr::IntLiteral(1)).unwrap();
+    assert_eq!(ty, TypeSet::singleton(BaseType::I64));
+  }
+
+  #[test]
+  fn unbound_variable_is_an_error() {
+    let mut locals = LocalTypeTable::new();
+    let functions = FunctionTypeTable::new();
+    assert!(infer_expr(&mut locals, &functions, &Expr::Variable("x".to_string())).is_err());
+  }
+
+  #[test]
+  fn binary_op_unifies_both_operands() {
+    let mut locals = LocalTypeTable::new();
+    locals.set("x", TypeSet::singleton(BaseType::I64));
+    let functions = FunctionTypeTable::new();
+    let expr = Expr::Binary {
+      op: "+".to_string(),
+      left: Box::new(Expr::Variable("x".to_string())),
+      right: Box::new(Expr::IntLiteral(1)),
+    };
+    let ty = infer_expr(&mut locals, &functions, &expr).unwrap();
+    assert_eq!(ty, TypeSet::singleton(BaseType::I64));
+  }
+
+  #[test]
+  fn binary_op_fails_with_context_on_a_type_mismatch() {
+    let mut locals = LocalTypeTable::new();
+    locals.set("x", TypeSet::singleton(BaseType::Bool));
+    let functions = FunctionTypeTable::new();
+    let expr = Expr::Binary {
+      op: "+".to_string(),
+      left: Box::new(Expr::Variable("x".to_string())),
+      right: Box::new(Expr::IntLiteral(1)),
+    };
+    let err = infer_expr(&mut locals, &functions, &expr).unwrap_err();
+    assert_eq!(err.context.last().unwrap().message, "while unifying the operands of `+`");
+  }
+
+  #[test]
+  fn call_recurses_into_each_argument_with_context() {
+    let mut locals = LocalTypeTable::new();
+    let mut functions = FunctionTypeTable::new();
+    functions.insert(
+      "f",
+      FunctionType { arg_types: vec![TypeSet::singleton(BaseType::I64)], return_type: TypeSet::singleton(BaseType::Bool) },
+    );
+    let expr = Expr::Call { function: "f".to_string(), args: vec![Expr::Variable("missing".to_string())] };
+    let err = infer_expr(&mut locals, &functions, &expr).unwrap_err();
+    assert_eq!(err.context.last().unwrap().message, "in argument 0 of `f`");
+  }
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+/// A base type a value can have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BaseType {
+  I32,
+  I64,
+  F32,
+  F64,
+  Bool,
+  String,
+}
+
+impl Display for BaseType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::I32 => write!(f, "i32"),
+      Self::I64 => write!(f, "i64"),
+      Self::F32 => write!(f, "f32"),
+      Self::F64 => write!(f, "f64"),
+      Self::Bool => write!(f, "bool"),
+      Self::String => write!(f, "String"),
+    }
+  }
+}
+
+/// The set of base types a value could still have. `All` means nothing has
+/// constrained it yet; unifying two `TypeSet`s intersects their candidates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeSet {
+  All,
+  Types(BTreeSet<BaseType>),
+}
+
+impl TypeSet {
+  pub fn singleton(ty: BaseType) -> Self {
+    Self::Types(BTreeSet::from([ty]))
+  }
+
+  /// Intersect two type sets, returning `None` if they share no candidate.
+  pub fn unify(&self, other: &Self) -> Option<Self> {
+    match (self, other) {
+      (Self::All, other) => Some(other.clone()),
+      (this, Self::All) => Some(this.clone()),
+      (Self::Types(a), Self::Types(b)) => {
+        let intersection: BTreeSet<_> = a.intersection(b).cloned().collect();
+        if intersection.is_empty() {
+          None
+        } else {
+          Some(Self::Types(intersection))
+        }
+      }
+    }
+  }
+}
+
+impl Display for TypeSet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::All => write!(f, "?"),
+      Self::Types(types) => {
+        let rendered = types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" | ");
+        write!(f, "{}", rendered)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unify_with_all_returns_the_other_side() {
+    let i32s = TypeSet::singleton(BaseType::I32);
+    assert_eq!(TypeSet::All.unify(&i32s), Some(i32s.clone()));
+    assert_eq!(i32s.unify(&TypeSet::All), Some(i32s));
+  }
+
+  #[test]
+  fn unify_intersects_overlapping_candidates() {
+    let numeric = TypeSet::Types(BTreeSet::from([BaseType::I32, BaseType::I64]));
+    let int64 = TypeSet::Types(BTreeSet::from([BaseType::I64, BaseType::F64]));
+    assert_eq!(numeric.unify(&int64), Some(TypeSet::singleton(BaseType::I64)));
+  }
+
+  #[test]
+  fn unify_fails_on_disjoint_candidates() {
+    let i32s = TypeSet::singleton(BaseType::I32);
+    let bools = TypeSet::singleton(BaseType::Bool);
+    assert_eq!(i32s.unify(&bools), None);
+  }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+/// Unify the current type of variable `name` with `with`, narrowing
+/// `locals` in place.
+pub fn unify_variable(locals: &mut LocalTypeTable, name: &str, with: TypeSet) -> Result<TypeSet, TypeInferenceError> {
+  let current = locals.get(name);
+  let unified = current.unify(&with).ok_or_else(|| {
+    TypeInferenceError::new(TypeInferenceErrorKind::CannotUnifyTypes { t1: current.clone(), t2: with.clone() })
+      .attach_context(format!("while unifying variable `{}`", name))
+  })?;
+  locals.set(name.to_string(), unified.clone());
+  Ok(unified)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unify_variable_narrows_an_unconstrained_variable() {
+    let mut locals = LocalTypeTable::new();
+    let result = unify_variable(&mut locals, "x", TypeSet::singleton(BaseType::I32)).unwrap();
+    assert_eq!(result, TypeSet::singleton(BaseType::I32));
+    assert_eq!(locals.get("x"), TypeSet::singleton(BaseType::I32));
+  }
+
+  #[test]
+  fn unify_variable_fails_on_a_conflicting_type() {
+    let mut locals = LocalTypeTable::new();
+    locals.set("x", TypeSet::singleton(BaseType::Bool));
+    let result = unify_variable(&mut locals, "x", TypeSet::singleton(BaseType::I32));
+    assert!(result.is_err());
+  }
+}
@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A cooperative cancellation signal shared across an execution. Cloning a
+/// `CancellationToken` shares the same underlying flag, so cancelling one
+/// handle is immediately observed by every other handle.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+  pub fn new() -> Self {
+    Self { cancelled: Arc::new(AtomicBool::new(false)) }
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::Relaxed);
+  }
+}
+
+/// The error produced when a budgeted operation is cancelled, exhausts its
+/// step budget, or exceeds its wall-clock timeout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aborted;
+
+impl std::fmt::Display for Aborted {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("provenance evaluation was aborted (cancelled, step budget exhausted, or timed out)")
+  }
+}
+
+impl std::error::Error for Aborted {}
+
+/// An optional cap on how much work a provenance query is allowed to do:
+/// a cooperative cancellation flag, a count of remaining tag operations,
+/// and/or a wall-clock deadline.
+#[derive(Clone, Debug)]
+pub struct ExecutionBudget {
+  token: CancellationToken,
+  remaining_steps: Option<Arc<AtomicU64>>,
+  deadline: Option<Instant>,
+}
+
+impl ExecutionBudget {
+  /// No cancellation, no step cap, no deadline.
+  pub fn unbounded() -> Self {
+    Self {
+      token: CancellationToken::new(),
+      remaining_steps: None,
+      deadline: None,
+    }
+  }
+
+  /// The token backing this budget, e.g. to hand to a Ctrl-C handler.
+  pub fn token(&self) -> CancellationToken {
+    self.token.clone()
+  }
+
+  /// Cap the number of tag operations this budget will allow.
+  pub fn with_budget(mut self, steps: u64) -> Self {
+    self.remaining_steps = Some(Arc::new(AtomicU64::new(steps)));
+    self
+  }
+
+  /// Cap the wall-clock time this budget will allow, starting now.
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.deadline = Some(Instant::now() + timeout);
+    self
+  }
+
+  /// Consume one step of budget and check for cancellation, an exhausted
+  /// step budget, or an exceeded deadline. Returns `Err(Aborted)` the first
+  /// time any of those conditions holds, latching cancellation for every
+  /// other clone of this budget too.
+  pub fn check(&self) -> Result<(), Aborted> {
+    if self.token.is_cancelled() {
+      return Err(Aborted);
+    }
+    if let Some(deadline) = self.deadline {
+      if Instant::now() >= deadline {
+        self.token.cancel();
+        return Err(Aborted);
+      }
+    }
+    if let Some(remaining) = &self.remaining_steps {
+      let had_budget = remaining
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+        .is_ok();
+      if !had_budget {
+        self.token.cancel();
+        return Err(Aborted);
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Default for ExecutionBudget {
+  fn default() -> Self {
+    Self::unbounded()
+  }
+}
+
+/// Install a process-wide Ctrl-C handler that cancels `token` when the
+/// user interrupts the process. Gated behind the `ctrlc` feature so library
+/// consumers that embed scallop without a CLI don't pull in a signal
+/// handling dependency just for this.
+///
+/// This is library-side plumbing only: nothing in this crate calls it. A
+/// CLI/driver binary is expected to call it once at startup with the token
+/// backing whatever `ExecutionBudget` it hands to the provenance it
+/// constructs, e.g.:
+///
+/// ```ignore
+/// let provenance = DiffTopKProofsProvenance::<_, Rc>::new(k).with_timeout(query_timeout);
+/// install_ctrlc_handler(provenance.cancellation_token())?;
+/// ```
+///
+/// This tree has no such binary (no `main.rs`/CLI crate checked in here),
+/// so there is no in-tree call site to wire this into; the doc example
+/// above is the wiring a driver would do.
+#[cfg(feature = "ctrlc")]
+pub fn install_ctrlc_handler(token: CancellationToken) -> Result<(), ctrlc::Error> {
+  ctrlc::set_handler(move || token.cancel())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unbounded_budget_never_aborts() {
+    let budget = ExecutionBudget::unbounded();
+    for _ in 0..1000 {
+      assert!(budget.check().is_ok());
+    }
+  }
+
+  #[test]
+  fn zero_step_budget_aborts_on_the_first_check() {
+    let budget = ExecutionBudget::unbounded().with_budget(0);
+    assert_eq!(budget.check(), Err(Aborted));
+  }
+
+  #[test]
+  fn step_budget_aborts_once_exhausted() {
+    let budget = ExecutionBudget::unbounded().with_budget(2);
+    assert!(budget.check().is_ok());
+    assert!(budget.check().is_ok());
+    assert_eq!(budget.check(), Err(Aborted));
+  }
+
+  #[test]
+  fn cancelling_the_token_aborts_every_clone() {
+    let budget = ExecutionBudget::unbounded();
+    let token = budget.token();
+    token.cancel();
+    assert_eq!(budget.check(), Err(Aborted));
+  }
+
+  #[test]
+  fn exhausting_the_budget_latches_cancellation_for_sibling_loops() {
+    let budget = ExecutionBudget::unbounded().with_budget(1);
+    let sibling = budget.clone();
+    assert!(budget.check().is_ok());
+    assert_eq!(budget.check(), Err(Aborted));
+    // `sibling` shares the same step counter and cancellation flag, so it
+    // observes the abort too even though it never called `check` itself.
+    assert_eq!(sibling.check(), Err(Aborted));
+  }
+}
@@ -1,6 +1,7 @@
 use itertools::Itertools;
 
 use super::*;
+use crate::runtime::cancellation::*;
 use crate::runtime::dynamic::*;
 use crate::runtime::statics::*;
 use crate::utils::*;
@@ -9,6 +10,7 @@ pub struct DiffTopKProofsProvenance<T: Clone, P: PointerFamily> {
   pub k: usize,
   pub diff_probs: P::Pointer<Vec<(f64, T)>>,
   pub disjunctions: Disjunctions,
+  pub budget: ExecutionBudget,
 }
 
 impl<T: Clone, P: PointerFamily> Clone for DiffTopKProofsProvenance<T, P> {
@@ -17,6 +19,7 @@ impl<T: Clone, P: PointerFamily> Clone for DiffTopKProofsProvenance<T, P> {
       k: self.k,
       diff_probs: P::new((&*self.diff_probs).clone()),
       disjunctions: self.disjunctions.clone(),
+      budget: self.budget.clone(),
     }
   }
 }
@@ -27,6 +30,7 @@ impl<T: Clone, P: PointerFamily> DiffTopKProofsProvenance<T, P> {
       k,
       diff_probs: P::new(Vec::new()),
       disjunctions: Disjunctions::new(),
+      budget: ExecutionBudget::unbounded(),
     }
   }
 
@@ -37,6 +41,68 @@ impl<T: Clone, P: PointerFamily> DiffTopKProofsProvenance<T, P> {
   pub fn set_k(&mut self, k: usize) {
     self.k = k;
   }
+
+  /// The budget to ask `top_k_add`/`top_k_mult`/`top_k_negate` for before
+  /// `simplify`-ing and truncating to `self.k` ourselves: enough headroom
+  /// that their own internal truncation doesn't drop a distinct proof to
+  /// make room for one later found to be a duplicate or absorbed.
+  fn slack_k(&self) -> usize {
+    self.k.saturating_mul(2).max(self.k + 1)
+  }
+
+  /// Cap the number of powerset subsets `dynamic_count`/`static_count` will
+  /// enumerate before aborting early. Each subset consumes one step; no
+  /// other operation (`add`, `mult`, `negate`, min/max/exists) consumes or
+  /// checks this budget.
+  pub fn with_budget(mut self, steps: u64) -> Self {
+    self.budget = self.budget.with_budget(steps);
+    self
+  }
+
+  /// Cap the wall-clock time `dynamic_count`/`static_count` will spend
+  /// enumerating powerset subsets before aborting early. The deadline is
+  /// checked only there; no other operation checks it.
+  pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+    self.budget = self.budget.with_timeout(timeout);
+    self
+  }
+
+  /// The cancellation token backing this provenance's budget, e.g. to wire
+  /// up to a Ctrl-C handler.
+  pub fn cancellation_token(&self) -> CancellationToken {
+    self.budget.token()
+  }
+
+}
+
+/// Enumerate the powerset of `0..len` as index sets, stopping early if
+/// `budget` is exhausted. Shared by `dynamic_count` and `static_count`,
+/// which differ only in how they wrap each chosen set into an element.
+///
+/// `dynamic_count`/`static_count` can't signal "aborted" through their
+/// return type, so a budget-exhausted truncation is surfaced loudly here
+/// instead of being returned as if it were a complete count: `debug_assert!`
+/// panics in debug builds (where the cost of a CI run noticing is cheap),
+/// since release builds shouldn't crash on a condition the caller opted
+/// into via `with_budget`/`with_timeout`. A release-mode caller that needs
+/// to distinguish a partial count from a complete one checks
+/// `cancellation_token().is_cancelled()` after the call.
+fn budgeted_powerset(len: usize, budget: &ExecutionBudget) -> Vec<Vec<usize>> {
+  let mut chosen_sets = vec![];
+  for chosen_set in (0..len).powerset() {
+    if budget.check().is_err() {
+      debug_assert!(
+        false,
+        "dynamic_count/static_count aborted partway through the powerset: the budget passed to \
+         with_budget/with_timeout ran out before every subset was counted, so the returned count \
+         elements are a partial result, not the true count. Check cancellation_token().is_cancelled() \
+         after the call if this run must distinguish a partial count from a complete one."
+      );
+      break;
+    }
+    chosen_sets.push(chosen_set);
+  }
+  chosen_sets
 }
 
 impl<T: Clone, P: PointerFamily> DNFContextTrait for DiffTopKProofsProvenance<T, P> {
@@ -105,7 +171,10 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
   }
 
   fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
-    self.top_k_add(t1, t2, self.k)
+    let mut t = self.top_k_add(t1, t2, self.slack_k());
+    t.simplify();
+    t.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    t
   }
 
   fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
@@ -113,11 +182,17 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
   }
 
   fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
-    self.top_k_mult(t1, t2, self.k)
+    let mut t = self.top_k_mult(t1, t2, self.slack_k());
+    t.simplify();
+    t.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    t
   }
 
   fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
-    Some(self.top_k_negate(t, self.k))
+    let mut negated = self.top_k_negate(t, self.slack_k());
+    negated.simplify();
+    negated.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    Some(negated)
   }
 
   fn weight(&self, t: &Self::Tag) -> f64 {
@@ -125,17 +200,28 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
     t.wmc(&RealSemiring::new(), &v)
   }
 
+  /// **Budget caveat:** the powerset this loop walks is exponential in
+  /// `batch.len()`, so it is the one place in this impl that can run away.
+  /// If the budget is exhausted partway through, this returns the `count`
+  /// elements accumulated so far rather than the complete set —
+  /// `Provenance::dynamic_count` can't return `Result` without changing
+  /// every implementor, so there is no well-typed way to signal "aborted"
+  /// through the return value itself. The `debug_assert!` in
+  /// `budgeted_powerset` makes that silent partiality loud in debug builds; callers in release builds that
+  /// care whether this ran to completion must check
+  /// `cancellation_token().is_cancelled()` themselves after the call.
   fn dynamic_count(&self, batch: DynamicElements<Self>) -> DynamicElements<Self> {
     if batch.is_empty() {
       vec![DynamicElement::new(0usize, self.one())]
     } else {
-      let mut elems = vec![];
-      for chosen_set in (0..batch.len()).powerset() {
-        let count = chosen_set.len();
-        let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
-        elems.push(DynamicElement::new(count, tag));
-      }
-      elems
+      budgeted_powerset(batch.len(), &self.budget)
+        .into_iter()
+        .map(|chosen_set| {
+          let count = chosen_set.len();
+          let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
+          DynamicElement::new(count, tag)
+        })
+        .collect()
     }
   }
 
@@ -178,17 +264,20 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
     vec![t, f]
   }
 
+  /// See the budget caveat on [`Self::dynamic_count`]: this walks the same
+  /// exponential powerset and truncates the same way on an exhausted budget.
   fn static_count<Tup: StaticTupleTrait>(&self, batch: StaticElements<Tup, Self>) -> StaticElements<usize, Self> {
     if batch.is_empty() {
       vec![StaticElement::new(0, self.one())]
     } else {
-      let mut elems = vec![];
-      for chosen_set in (0..batch.len()).powerset() {
-        let count = chosen_set.len();
-        let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
-        elems.push(StaticElement::new(count, tag));
-      }
-      elems
+      budgeted_powerset(batch.len(), &self.budget)
+        .into_iter()
+        .map(|chosen_set| {
+          let count = chosen_set.len();
+          let tag = self.top_k_tag_of_chosen_set(batch.iter().map(|e| &e.tag), &chosen_set, self.k);
+          StaticElement::new(count, tag)
+        })
+        .collect()
     }
   }
 
@@ -231,3 +320,35 @@ impl<T: Clone + 'static, P: PointerFamily> Provenance for DiffTopKProofsProvenan
     vec![t, f]
   }
 }
+
+// `add`/`mult`/`negate` (and `dynamic_count`/`static_count`'s interaction
+// with the `Provenance`/`DNFContextTrait` default methods) aren't tested at
+// the `DiffTopKProofsProvenance` level: constructing one needs a concrete
+// `PointerFamily`, which — like `Provenance`/`DNFContextTrait` themselves —
+// is external and not defined in this tree. The pieces that are
+// constructible here are covered instead: `budgeted_powerset` below, plus
+// `dnf::tests::simplify_then_truncate_matches_the_order_add_mult_negate_use`
+// for the simplify-then-truncate interaction `add`/`mult`/`negate` rely on,
+// and `disjunction::tests` for `has_disjunction_conflict`'s conflict detection.
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn budgeted_powerset_enumerates_everything_when_unbounded() {
+    let chosen_sets = budgeted_powerset(3, &ExecutionBudget::unbounded());
+    // The powerset of a 3-element set has 2^3 = 8 subsets.
+    assert_eq!(chosen_sets.len(), 8);
+  }
+
+  #[test]
+  #[should_panic(expected = "aborted partway through the powerset")]
+  fn budgeted_powerset_truncates_and_loudly_reports_when_the_budget_runs_out() {
+    // A 4-element set has 16 subsets; a budget of 2 can't check all of them,
+    // so the loop must break early instead of silently returning a complete
+    // powerset. The `debug_assert!` that reports the truncation fires in
+    // this debug test build, which is exactly the loud signal being tested.
+    let budget = ExecutionBudget::unbounded().with_budget(2);
+    budgeted_powerset(4, &budget);
+  }
+}
@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+/// Tracks which facts are mutually exclusive with each other.
+///
+/// Facts registered under the same `disjunction_id` are unioned into one
+/// exclusion group via a union-find structure, so `has_conflict` resolves
+/// in near-constant time per fact.
+#[derive(Clone, Debug, Default)]
+pub struct Disjunctions {
+  /// Union-find parent pointers, indexed by a dense internal group id. A
+  /// negative entry `-s` marks a root whose tree has size `s`; a
+  /// non-negative entry is the index of the node's parent.
+  parent_or_size: RefCell<Vec<isize>>,
+
+  /// Maps a caller-provided `disjunction_id` to the internal group id
+  /// allocated for it.
+  disjunction_id_to_group: HashMap<usize, usize>,
+
+  /// Maps a fact id to the internal group id it was unioned into.
+  fact_id_to_group: HashMap<usize, usize>,
+}
+
+impl Disjunctions {
+  pub fn new() -> Self {
+    Self {
+      parent_or_size: RefCell::new(Vec::new()),
+      disjunction_id_to_group: HashMap::new(),
+      fact_id_to_group: HashMap::new(),
+    }
+  }
+
+  /// Allocate a new singleton group and return its internal id.
+  fn new_group(&self) -> usize {
+    let mut parent_or_size = self.parent_or_size.borrow_mut();
+    let id = parent_or_size.len();
+    parent_or_size.push(-1);
+    id
+  }
+
+  /// Find the root of group `x`, compressing the path to it.
+  fn find(&self, x: usize) -> usize {
+    let parent = self.parent_or_size.borrow()[x];
+    if parent < 0 {
+      x
+    } else {
+      let root = self.find(parent as usize);
+      self.parent_or_size.borrow_mut()[x] = root as isize;
+      root
+    }
+  }
+
+  /// Union the groups of `x` and `y`, attaching the smaller tree to the
+  /// root of the bigger one.
+  fn union(&self, x: usize, y: usize) {
+    let (mut root_x, mut root_y) = (self.find(x), self.find(y));
+    if root_x == root_y {
+      return;
+    }
+    let mut parent_or_size = self.parent_or_size.borrow_mut();
+    if -parent_or_size[root_x] < -parent_or_size[root_y] {
+      std::mem::swap(&mut root_x, &mut root_y);
+    }
+    parent_or_size[root_x] += parent_or_size[root_y];
+    parent_or_size[root_y] = root_x as isize;
+  }
+
+  /// Record that `fact_id` belongs to the mutual-exclusion group
+  /// identified by `disjunction_id`, unioning it with any other fact
+  /// already recorded under that same `disjunction_id`.
+  pub fn add_disjunction(&mut self, disjunction_id: usize, fact_id: usize) {
+    let disjunction_group = match self.disjunction_id_to_group.get(&disjunction_id) {
+      Some(&group) => group,
+      None => {
+        let group = self.new_group();
+        self.disjunction_id_to_group.insert(disjunction_id, group);
+        group
+      }
+    };
+
+    let fact_group = match self.fact_id_to_group.get(&fact_id) {
+      Some(&group) => group,
+      None => self.new_group(),
+    };
+
+    self.union(fact_group, disjunction_group);
+    let root = self.find(fact_group);
+    self.fact_id_to_group.insert(fact_id, root);
+  }
+
+  /// Returns `true` as soon as two facts in `pos_facts` resolve to the same
+  /// exclusion group, meaning they were declared mutually exclusive.
+  pub fn has_conflict(&self, pos_facts: &std::collections::BTreeSet<usize>) -> bool {
+    let mut seen_roots = HashSet::new();
+    for fact_id in pos_facts {
+      if let Some(&group) = self.fact_id_to_group.get(fact_id) {
+        let root = self.find(group);
+        if !seen_roots.insert(root) {
+          return true;
+        }
+      }
+    }
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn set(ids: impl IntoIterator<Item = usize>) -> std::collections::BTreeSet<usize> {
+    ids.into_iter().collect()
+  }
+
+  #[test]
+  fn facts_in_same_disjunction_conflict() {
+    let mut d = Disjunctions::new();
+    d.add_disjunction(0, 1);
+    d.add_disjunction(0, 2);
+    d.add_disjunction(0, 3);
+    assert!(d.has_conflict(&set([1, 2])));
+    assert!(d.has_conflict(&set([1, 2, 3])));
+  }
+
+  #[test]
+  fn facts_in_different_disjunctions_do_not_conflict() {
+    let mut d = Disjunctions::new();
+    d.add_disjunction(0, 1);
+    d.add_disjunction(0, 2);
+    d.add_disjunction(1, 3);
+    d.add_disjunction(1, 4);
+    assert!(!d.has_conflict(&set([1, 3])));
+    assert!(!d.has_conflict(&set([2, 4])));
+  }
+
+  #[test]
+  fn facts_with_no_declared_disjunction_never_conflict() {
+    let d = Disjunctions::new();
+    assert!(!d.has_conflict(&set([1, 2])));
+  }
+
+  #[test]
+  fn a_single_fact_never_conflicts_with_itself() {
+    let mut d = Disjunctions::new();
+    d.add_disjunction(0, 1);
+    assert!(!d.has_conflict(&set([1])));
+  }
+
+  #[test]
+  fn unioning_two_previously_separate_groups_merges_their_conflicts() {
+    // Facts 1/2 are declared exclusive under id 0, and facts 2/3 under id
+    // 1; since fact 2 is shared, all three end up in one merged group.
+    let mut d = Disjunctions::new();
+    d.add_disjunction(0, 1);
+    d.add_disjunction(0, 2);
+    d.add_disjunction(1, 2);
+    d.add_disjunction(1, 3);
+    assert!(d.has_conflict(&set([1, 3])));
+  }
+}
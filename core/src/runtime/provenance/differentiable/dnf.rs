@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+/// A single conjunctive clause: the set of fact ids that must all hold.
+pub type Clause = BTreeSet<usize>;
+
+/// A formula in disjunctive normal form over fact ids: a disjunction of
+/// `Clause`s, each of which is a positive conjunction of fact ids standing
+/// for one proof of the tagged fact.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DNFFormula {
+  pub clauses: Vec<Clause>,
+}
+
+impl DNFFormula {
+  /// The formula `false`: satisfied by nothing.
+  pub fn zero() -> Self {
+    Self { clauses: vec![] }
+  }
+
+  /// The formula `true`: satisfied by the empty conjunction.
+  pub fn one() -> Self {
+    Self { clauses: vec![Clause::new()] }
+  }
+
+  /// The formula containing only the proof `{fact_id}`.
+  pub fn singleton(fact_id: usize) -> Self {
+    Self { clauses: vec![Clause::from_iter([fact_id])] }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.clauses.is_empty()
+  }
+
+  /// Drop clauses that are logically redundant given the rest of the
+  /// formula, in place: absorption (`A ∨ (A ∧ B) = A`), exact duplicates,
+  /// and collapsing to `true` if the empty clause is present.
+  pub fn simplify(&mut self) {
+    if self.clauses.iter().any(|clause| clause.is_empty()) {
+      self.clauses = vec![Clause::new()];
+      return;
+    }
+
+    // Sorting by ascending cardinality guarantees that by the time we
+    // consider a clause, every clause small enough to possibly absorb it
+    // has already been checked and kept.
+    self.clauses.sort_by_key(|clause| clause.len());
+
+    let mut kept: Vec<Clause> = Vec::with_capacity(self.clauses.len());
+    'clauses: for clause in self.clauses.drain(..) {
+      for smaller in &kept {
+        if smaller.len() == clause.len() {
+          if smaller == &clause {
+            continue 'clauses; // exact duplicate
+          }
+        } else if smaller.is_subset(&clause) {
+          continue 'clauses; // `smaller` absorbs `clause`
+        }
+      }
+      kept.push(clause);
+    }
+    self.clauses = kept;
+  }
+
+  /// Keep only the `k` clauses with the highest probability under
+  /// `prob_of`, where a clause's probability is the product of its facts'.
+  ///
+  /// Meant to run after `simplify`, not before: `top_k_add`/`top_k_mult`/
+  /// `top_k_negate` truncate to `k` internally, so calling them with extra
+  /// headroom, then `simplify`-ing, then truncating here for real is what
+  /// keeps a duplicate or dominated clause from crowding out a distinct
+  /// proof before it has a chance to be absorbed.
+  pub fn truncate_to_k(&mut self, k: usize, prob_of: &impl Fn(&usize) -> f64) {
+    self.clauses.sort_by(|a, b| {
+      let pa: f64 = a.iter().map(prob_of).product();
+      let pb: f64 = b.iter().map(prob_of).product();
+      pb.partial_cmp(&pa).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    self.clauses.truncate(k);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn clause(ids: impl IntoIterator<Item = usize>) -> Clause {
+    ids.into_iter().collect()
+  }
+
+  #[test]
+  fn simplify_drops_exact_duplicates() {
+    let mut f = DNFFormula { clauses: vec![clause([1, 2]), clause([1, 2])] };
+    f.simplify();
+    assert_eq!(f.clauses, vec![clause([1, 2])]);
+  }
+
+  #[test]
+  fn simplify_drops_clauses_absorbed_by_a_subset() {
+    // `{1}` absorbs `{1, 2}` since `1 ∨ (1 ∧ 2) = 1`.
+    let mut f = DNFFormula { clauses: vec![clause([1, 2]), clause([1])] };
+    f.simplify();
+    assert_eq!(f.clauses, vec![clause([1])]);
+  }
+
+  #[test]
+  fn simplify_keeps_incomparable_clauses() {
+    let mut f = DNFFormula { clauses: vec![clause([1, 2]), clause([3, 4])] };
+    f.simplify();
+    assert_eq!(f.clauses.len(), 2);
+  }
+
+  #[test]
+  fn simplify_collapses_to_true_when_empty_clause_present() {
+    let mut f = DNFFormula { clauses: vec![clause([1, 2]), Clause::new()] };
+    f.simplify();
+    assert_eq!(f, DNFFormula::one());
+  }
+
+  #[test]
+  fn truncate_to_k_keeps_the_highest_probability_clauses() {
+    let prob_of = |id: &usize| match id {
+      1 => 0.9,
+      2 => 0.1,
+      3 => 0.5,
+      _ => unreachable!(),
+    };
+    let mut f = DNFFormula { clauses: vec![clause([2]), clause([1]), clause([3])] };
+    f.truncate_to_k(2, &prob_of);
+    assert_eq!(f.clauses, vec![clause([1]), clause([3])]);
+  }
+
+  #[test]
+  fn simplify_then_truncate_matches_the_order_add_mult_negate_use() {
+    // `{1}` appears as both an exact duplicate and as the clause that
+    // absorbs `{1, 4}`, so the formula holds only 3 distinct proofs even
+    // though it has 5 clauses. Truncating to k=3 before simplifying would
+    // keep `{1}`, its duplicate and `{2}`, spending a slot on the duplicate
+    // and dropping the genuinely distinct `{3}` in its favor; this
+    // is exactly the bug `add`/`mult`/`negate` avoid by simplifying first.
+    let prob_of = |id: &usize| match id {
+      1 => 0.9,
+      2 => 0.5,
+      3 => 0.4,
+      4 => 0.1,
+      _ => unreachable!(),
+    };
+    let mut f = DNFFormula {
+      clauses: vec![clause([1]), clause([1]), clause([1, 4]), clause([2]), clause([3])],
+    };
+    f.simplify();
+    f.truncate_to_k(3, &prob_of);
+    assert_eq!(f.clauses, vec![clause([1]), clause([2]), clause([3])]);
+  }
+}
@@ -0,0 +1,216 @@
+use super::super::differentiable::disjunction::Disjunctions;
+use super::super::differentiable::dnf::DNFFormula;
+use super::super::differentiable::semiring::Semiring;
+use crate::common::rational::Rational;
+use crate::runtime::dynamic::*;
+use crate::runtime::statics::*;
+use crate::utils::*;
+
+/// A semiring over exact rationals: its element is the bare `Rational`
+/// itself, mirroring how `RealSemiring`'s element is a bare `f64` — there's
+/// nothing else to carry since this provenance doesn't track gradients.
+pub struct RationalSemiring;
+
+impl RationalSemiring {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl Semiring for RationalSemiring {
+  type Element = Rational;
+
+  fn zero(&self) -> Self::Element {
+    Rational::zero()
+  }
+
+  fn one(&self) -> Self::Element {
+    Rational::one()
+  }
+
+  fn add(&self, a: Self::Element, b: Self::Element) -> Self::Element {
+    a + b
+  }
+
+  fn mult(&self, a: Self::Element, b: Self::Element) -> Self::Element {
+    a * b
+  }
+}
+
+/// The input tag of a fact under `RationalTopKProofsProvenance`: an exact
+/// probability, plus an optional id of the set of facts it is mutually
+/// exclusive with.
+#[derive(Clone, Debug)]
+pub struct InputExclusiveExactProb<T: Clone> {
+  pub prob: Rational,
+  pub tag: T,
+  pub exclusion: Option<usize>,
+}
+
+/// The output tag of `RationalTopKProofsProvenance`: the exact probability
+/// together with a lossy `f64` projection of it, for display or for
+/// comparing against the floating-point provenance's results.
+#[derive(Clone, Debug)]
+pub struct OutputExactProb {
+  pub exact: Rational,
+  pub approx: f64,
+}
+
+/// A sibling of `DiffTopKProofsProvenance` that carries exact rational
+/// probabilities instead of `f64`, so repeated `add`/`mult` over many
+/// clauses never accumulates floating-point rounding error. Doesn't track
+/// gradients: there is no `diff_probs`, only the exact probability of each
+/// fact.
+pub struct RationalTopKProofsProvenance<T: Clone, P: PointerFamily> {
+  pub k: usize,
+  pub probs: P::Pointer<Vec<(Rational, T)>>,
+  pub disjunctions: Disjunctions,
+}
+
+impl<T: Clone, P: PointerFamily> Clone for RationalTopKProofsProvenance<T, P> {
+  fn clone(&self) -> Self {
+    Self {
+      k: self.k,
+      probs: P::new((&*self.probs).clone()),
+      disjunctions: self.disjunctions.clone(),
+    }
+  }
+}
+
+impl<T: Clone, P: PointerFamily> RationalTopKProofsProvenance<T, P> {
+  pub fn new(k: usize) -> Self {
+    Self {
+      k,
+      probs: P::new(Vec::new()),
+      disjunctions: Disjunctions::new(),
+    }
+  }
+
+  pub fn set_k(&mut self, k: usize) {
+    self.k = k;
+  }
+
+  /// The budget to ask `top_k_add`/`top_k_mult`/`top_k_negate` for before
+  /// `simplify`-ing and truncating to `self.k` ourselves: enough headroom
+  /// that their own internal truncation doesn't drop a distinct proof to
+  /// make room for one later found to be a duplicate or absorbed.
+  fn slack_k(&self) -> usize {
+    self.k.saturating_mul(2).max(self.k + 1)
+  }
+}
+
+impl<T: Clone, P: PointerFamily> DNFContextTrait for RationalTopKProofsProvenance<T, P> {
+  fn fact_probability(&self, id: &usize) -> f64 {
+    self.probs[*id].0.to_f64()
+  }
+
+  fn has_disjunction_conflict(&self, pos_facts: &std::collections::BTreeSet<usize>) -> bool {
+    self.disjunctions.has_conflict(pos_facts)
+  }
+}
+
+impl<T: Clone + 'static, P: PointerFamily> Provenance for RationalTopKProofsProvenance<T, P> {
+  type Tag = DNFFormula;
+
+  type InputTag = InputExclusiveExactProb<T>;
+
+  type OutputTag = OutputExactProb;
+
+  fn name() -> &'static str {
+    "rational-top-k-proofs"
+  }
+
+  fn tagging_fn(&mut self, input_tag: Self::InputTag) -> Self::Tag {
+    let InputExclusiveExactProb { prob, tag, exclusion } = input_tag;
+
+    let fact_id = self.probs.len();
+    P::get_mut(&mut self.probs).push((prob, tag));
+
+    if let Some(disjunction_id) = exclusion {
+      self.disjunctions.add_disjunction(disjunction_id, fact_id);
+    }
+
+    DNFFormula::singleton(fact_id)
+  }
+
+  fn recover_fn(&self, t: &Self::Tag) -> Self::OutputTag {
+    let v = |id: &usize| self.probs[*id].0.clone();
+    let exact = t.wmc(&RationalSemiring::new(), &v);
+    let approx = exact.to_f64();
+    OutputExactProb { exact, approx }
+  }
+
+  fn discard(&self, t: &Self::Tag) -> bool {
+    t.is_empty()
+  }
+
+  fn zero(&self) -> Self::Tag {
+    DNFFormula::zero()
+  }
+
+  fn one(&self) -> Self::Tag {
+    DNFFormula::one()
+  }
+
+  fn add(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    let mut t = self.top_k_add(t1, t2, self.slack_k());
+    t.simplify();
+    t.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    t
+  }
+
+  fn saturated(&self, t_old: &Self::Tag, t_new: &Self::Tag) -> bool {
+    t_old == t_new
+  }
+
+  fn mult(&self, t1: &Self::Tag, t2: &Self::Tag) -> Self::Tag {
+    let mut t = self.top_k_mult(t1, t2, self.slack_k());
+    t.simplify();
+    t.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    t
+  }
+
+  fn negate(&self, t: &Self::Tag) -> Option<Self::Tag> {
+    let mut negated = self.top_k_negate(t, self.slack_k());
+    negated.simplify();
+    negated.truncate_to_k(self.k, &|id| self.fact_probability(id));
+    Some(negated)
+  }
+
+  fn weight(&self, t: &Self::Tag) -> f64 {
+    let v = |id: &usize| self.probs[*id].0.clone();
+    t.wmc(&RationalSemiring::new(), &v).to_f64()
+  }
+}
+
+// `add`/`mult`/`negate` above are thin wrappers around `top_k_add`/
+// `top_k_mult`/`top_k_negate` (from the external `DNFContextTrait` default
+// impls, not present in this tree) followed by `simplify`+`truncate_to_k`;
+// and `has_disjunction_conflict` only delegates to `Disjunctions::has_conflict`.
+// Exercising those end-to-end would mean constructing a
+// `RationalTopKProofsProvenance<T, P>`, which needs a concrete `PointerFamily`
+// — also external, also absent here — so the simplify-then-truncate
+// interaction and disjunction-conflict detection are covered at the level
+// that's actually constructible in this tree: see
+// `differentiable::dnf::tests::simplify_then_truncate_matches_the_order_add_mult_negate_use`
+// and `differentiable::disjunction::tests`.
+#[cfg(test)]
+mod tests {
+  use super::super::super::differentiable::dnf::Clause;
+  use super::*;
+
+  #[test]
+  fn wmc_accounts_for_facts_shared_across_clauses() {
+    // `{0, 1}` and `{0, 2}` share fact `0`, so they aren't independent: the
+    // true probability is p0 * (p1 + p2 - p1*p2), not the p1/q1-style
+    // inclusion-exclusion you'd get by treating the two clauses as
+    // independent events.
+    let half = Rational::new(1, 2);
+    let v = move |_: &usize| half.clone();
+    let formula = DNFFormula {
+      clauses: vec![Clause::from_iter([0, 1]), Clause::from_iter([0, 2])],
+    };
+    let exact = formula.wmc(&RationalSemiring::new(), &v);
+    assert_eq!(exact, Rational::new(3, 8));
+  }
+}